@@ -1,13 +1,18 @@
 #![no_std]
 #![feature(ip_in_core)]
 
+extern crate alloc;
+
 mod inner;
+mod reactor;
 
 use crate::inner::{FromInner, IntoInner};
 use core::ffi::c_void;
 use core::future;
 use core::net::{SocketAddr, SocketAddrV4, SocketAddrV6};
-use core::task::Poll;
+use core::task::{Context, Poll};
+
+pub use crate::reactor::poll as poll_reactor;
 
 #[derive(Debug)]
 pub struct Socket(core::ffi::c_int);
@@ -24,6 +29,16 @@ pub struct TcpListener {
     inner: Socket,
 }
 
+impl TcpStream {
+    pub async fn connect(addr: &SocketAddr) -> Result<TcpStream, IOError> {
+        let sock = Socket::new(addr, esp_idf_sys::SOCK_STREAM as core::ffi::c_int)?;
+        sock.set_nonblocking()?;
+        let mut connecting = false;
+        future::poll_fn(|cx| sock.poll_connect(cx, addr, &mut connecting)).await?;
+        Ok(TcpStream { inner: sock })
+    }
+}
+
 impl TcpListener {
     pub fn bind(addr: &SocketAddr) -> Result<TcpListener, IOError> {
         let sock = Socket::new(addr, esp_idf_sys::SOCK_STREAM as core::ffi::c_int)?;
@@ -42,7 +57,7 @@ impl TcpListener {
     }
 
     pub async fn accept(&self) -> Result<(TcpStream, SocketAddr), IOError> {
-        let (stream, addr) = future::poll_fn(|_cx| self.inner.poll_accept()).await?;
+        let (stream, addr) = future::poll_fn(|cx| self.inner.poll_accept(cx)).await?;
         stream.inner.set_nonblocking()?;
         Ok((stream, addr))
     }
@@ -98,33 +113,195 @@ impl Socket {
         Ok(())
     }
 
-    pub fn poll_accept(&self) -> Poll<Result<(TcpStream, SocketAddr), IOError>> {
+    pub fn poll_connect(
+        &self,
+        cx: &mut Context<'_>,
+        addr: &SocketAddr,
+        connecting: &mut bool,
+    ) -> Poll<Result<(), IOError>> {
+        if !*connecting {
+            unsafe {
+                let (sockaddr, addr_len) = addr.into_inner();
+                match cvt(esp_idf_sys::lwip_connect(self.0, sockaddr.as_ptr(), addr_len)) {
+                    Ok(_) => return Poll::Ready(Ok(())),
+                    Err(errno) if errno == esp_idf_sys::EINPROGRESS as i32 => {
+                        *connecting = true;
+                        reactor::register_writer(self.0, cx.waker());
+                        return Poll::Pending;
+                    }
+                    Err(errno) => return Poll::Ready(Err(errno)),
+                }
+            }
+        }
+
+        match self.is_writable() {
+            Ok(true) => {}
+            Ok(false) => {
+                reactor::register_writer(self.0, cx.waker());
+                return Poll::Pending;
+            }
+            Err(errno) => return Poll::Ready(Err(errno)),
+        }
+
+        match self.getsockopt::<core::ffi::c_int>(
+            esp_idf_sys::SOL_SOCKET as core::ffi::c_int,
+            esp_idf_sys::SO_ERROR as core::ffi::c_int,
+        ) {
+            Ok(0) => Poll::Ready(Ok(())),
+            Ok(errno) if errno == esp_idf_sys::EISCONN as i32 => Poll::Ready(Ok(())),
+            Ok(errno) => Poll::Ready(Err(errno)),
+            Err(errno) => Poll::Ready(Err(errno)),
+        }
+    }
+
+    /// Check, without blocking, whether this socket is currently writable. Used by
+    /// `poll_connect` to avoid trusting `SO_ERROR` on a spurious re-poll that
+    /// arrived before the connect handshake actually finished.
+    fn is_writable(&self) -> Result<bool, IOError> {
+        let mut write_fds: esp_idf_sys::fd_set = unsafe { core::mem::zeroed() };
+        unsafe { esp_idf_sys::lwip_fd_set(self.0, &mut write_fds) };
+        let mut timeout = esp_idf_sys::timeval {
+            tv_sec: 0,
+            tv_usec: 0,
+        };
+        let ready = cvt(unsafe {
+            esp_idf_sys::lwip_select(
+                self.0 + 1,
+                core::ptr::null_mut(),
+                &mut write_fds,
+                core::ptr::null_mut(),
+                &mut timeout,
+            )
+        })?;
+        Ok(ready > 0 && unsafe { esp_idf_sys::lwip_fd_isset(self.0, &mut write_fds) } != 0)
+    }
+
+    pub fn poll_accept(
+        &self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(TcpStream, SocketAddr), IOError>> {
         let mut storage: esp_idf_sys::sockaddr_storage = unsafe { core::mem::zeroed() };
         let mut len = core::mem::size_of_val(&storage) as esp_idf_sys::socklen_t;
-        cvt_poll(unsafe {
+        let poll = cvt_poll(unsafe {
             esp_idf_sys::lwip_accept(
                 self.0,
                 &mut storage as *mut esp_idf_sys::sockaddr_storage as *mut esp_idf_sys::sockaddr,
                 &mut len,
             )
-        })?
-        .map(|fd| {
+        });
+        if poll.is_pending() {
+            reactor::register_reader(self.0, cx.waker());
+        }
+        poll?.map(|fd| {
             let sock = Socket(fd);
             let addr = sockaddr_to_addr(&storage, len as usize)?;
             Ok((TcpStream { inner: sock }, addr))
         })
     }
 
-    pub fn poll_read(&self, buf: &mut [u8]) -> Poll<Result<i32, IOError>> {
-        cvt_poll(unsafe {
+    pub fn poll_read(&self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<i32, IOError>> {
+        let poll = cvt_poll(unsafe {
             esp_idf_sys::lwip_read(self.0, buf.as_mut_ptr() as *mut c_void, buf.len())
-        } as i32)
+        } as i32);
+        if poll.is_pending() {
+            reactor::register_reader(self.0, cx.waker());
+        }
+        poll
     }
 
-    pub fn poll_write(&self, buf: &[u8]) -> Poll<Result<i32, IOError>> {
-        cvt_poll(unsafe {
+    pub fn poll_write(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<i32, IOError>> {
+        let poll = cvt_poll(unsafe {
             esp_idf_sys::lwip_write(self.0, buf.as_ptr() as *const c_void, buf.len())
-        } as i32)
+        } as i32);
+        if poll.is_pending() {
+            reactor::register_writer(self.0, cx.waker());
+        }
+        poll
+    }
+
+    pub fn poll_read_vectored(
+        &self,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<Result<i32, IOError>> {
+        let poll = cvt_poll(unsafe {
+            esp_idf_sys::lwip_readv(
+                self.0,
+                bufs.as_mut_ptr() as *mut esp_idf_sys::iovec,
+                bufs.len() as core::ffi::c_int,
+            )
+        } as i32);
+        if poll.is_pending() {
+            reactor::register_reader(self.0, cx.waker());
+        }
+        poll
+    }
+
+    pub fn poll_write_vectored(
+        &self,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<Result<i32, IOError>> {
+        let poll = cvt_poll(unsafe {
+            esp_idf_sys::lwip_writev(
+                self.0,
+                bufs.as_ptr() as *const esp_idf_sys::iovec,
+                bufs.len() as core::ffi::c_int,
+            )
+        } as i32);
+        if poll.is_pending() {
+            reactor::register_writer(self.0, cx.waker());
+        }
+        poll
+    }
+
+    pub fn poll_send_to(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+        addr: &SocketAddr,
+    ) -> Poll<Result<i32, IOError>> {
+        let (sockaddr, addr_len) = addr.into_inner();
+        let poll = cvt_poll(unsafe {
+            esp_idf_sys::lwip_sendto(
+                self.0,
+                buf.as_ptr() as *const c_void,
+                buf.len(),
+                0,
+                sockaddr.as_ptr(),
+                addr_len,
+            )
+        } as i32);
+        if poll.is_pending() {
+            reactor::register_writer(self.0, cx.waker());
+        }
+        poll
+    }
+
+    pub fn poll_recv_from(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<(i32, SocketAddr), IOError>> {
+        let mut storage: esp_idf_sys::sockaddr_storage = unsafe { core::mem::zeroed() };
+        let mut len = core::mem::size_of_val(&storage) as esp_idf_sys::socklen_t;
+        let poll = cvt_poll(unsafe {
+            esp_idf_sys::lwip_recvfrom(
+                self.0,
+                buf.as_mut_ptr() as *mut c_void,
+                buf.len(),
+                0,
+                &mut storage as *mut esp_idf_sys::sockaddr_storage as *mut esp_idf_sys::sockaddr,
+                &mut len,
+            )
+        } as i32);
+        if poll.is_pending() {
+            reactor::register_reader(self.0, cx.waker());
+        }
+        poll?.map(|n| {
+            let addr = sockaddr_to_addr(&storage, len as usize)?;
+            Ok((n, addr))
+        })
     }
 
     pub fn setsockopt<T>(
@@ -144,22 +321,344 @@ impl Socket {
             Ok(())
         }
     }
+
+    pub fn getsockopt<T>(
+        &self,
+        level: core::ffi::c_int,
+        option_name: core::ffi::c_int,
+    ) -> Result<T, IOError> {
+        unsafe {
+            let mut option_value: T = core::mem::zeroed();
+            let mut option_len = core::mem::size_of::<T>() as esp_idf_sys::socklen_t;
+            cvt(esp_idf_sys::lwip_getsockopt(
+                self.0,
+                level,
+                option_name,
+                &mut option_value as *mut T as *mut _,
+                &mut option_len,
+            ))?;
+            Ok(option_value)
+        }
+    }
 }
 
 impl Drop for Socket {
     fn drop(&mut self) {
+        reactor::deregister(self.0);
         unsafe {
             esp_idf_sys::lwip_close(self.0);
         }
     }
 }
 
+/// A trait for borrowing a raw lwIP socket descriptor.
+pub trait AsRawFd {
+    fn as_raw_fd(&self) -> core::ffi::c_int;
+}
+
+/// A trait for consuming a type and releasing ownership of its raw lwIP socket descriptor.
+///
+/// The returned descriptor is no longer closed by `Drop`; the caller becomes responsible
+/// for it.
+pub trait IntoRawFd {
+    fn into_raw_fd(self) -> core::ffi::c_int;
+}
+
+/// A trait for constructing a type from a raw lwIP socket descriptor.
+pub trait FromRawFd {
+    /// # Safety
+    /// `fd` must be a valid, open socket descriptor that is not owned elsewhere, since
+    /// the returned value will close it on `Drop`.
+    unsafe fn from_raw_fd(fd: core::ffi::c_int) -> Self;
+}
+
+impl AsRawFd for Socket {
+    fn as_raw_fd(&self) -> core::ffi::c_int {
+        self.0
+    }
+}
+
+impl IntoRawFd for Socket {
+    fn into_raw_fd(self) -> core::ffi::c_int {
+        let fd = self.0;
+        reactor::deregister(fd);
+        core::mem::forget(self);
+        fd
+    }
+}
+
+impl FromRawFd for Socket {
+    unsafe fn from_raw_fd(fd: core::ffi::c_int) -> Socket {
+        Socket(fd)
+    }
+}
+
+impl AsRawFd for TcpStream {
+    fn as_raw_fd(&self) -> core::ffi::c_int {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl IntoRawFd for TcpStream {
+    fn into_raw_fd(self) -> core::ffi::c_int {
+        self.inner.into_raw_fd()
+    }
+}
+
+impl FromRawFd for TcpStream {
+    unsafe fn from_raw_fd(fd: core::ffi::c_int) -> TcpStream {
+        TcpStream {
+            inner: Socket::from_raw_fd(fd),
+        }
+    }
+}
+
+impl AsRawFd for TcpListener {
+    fn as_raw_fd(&self) -> core::ffi::c_int {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl IntoRawFd for TcpListener {
+    fn into_raw_fd(self) -> core::ffi::c_int {
+        self.inner.into_raw_fd()
+    }
+}
+
+impl FromRawFd for TcpListener {
+    unsafe fn from_raw_fd(fd: core::ffi::c_int) -> TcpListener {
+        TcpListener {
+            inner: Socket::from_raw_fd(fd),
+        }
+    }
+}
+
+/// What to shut down on a [`TcpStream`], passed to [`TcpStream::shutdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shutdown {
+    Read,
+    Write,
+    Both,
+}
+
 impl TcpStream {
     pub async fn read(&self, buf: &mut [u8]) -> Result<i32, IOError> {
-        future::poll_fn(|_cx| self.inner.poll_read(buf)).await
+        future::poll_fn(|cx| self.inner.poll_read(cx, buf)).await
     }
     pub async fn write(&self, buf: &[u8]) -> Result<i32, IOError> {
-        future::poll_fn(|_cx| self.inner.poll_write(buf)).await
+        future::poll_fn(|cx| self.inner.poll_write(cx, buf)).await
+    }
+
+    pub async fn read_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> Result<i32, IOError> {
+        future::poll_fn(|cx| self.inner.poll_read_vectored(cx, bufs)).await
+    }
+
+    pub async fn write_vectored(&self, bufs: &[IoSlice<'_>]) -> Result<i32, IOError> {
+        future::poll_fn(|cx| self.inner.poll_write_vectored(cx, bufs)).await
+    }
+
+    pub fn shutdown(&self, how: Shutdown) -> Result<(), IOError> {
+        let how = match how {
+            Shutdown::Read => esp_idf_sys::SHUT_RD,
+            Shutdown::Write => esp_idf_sys::SHUT_WR,
+            Shutdown::Both => esp_idf_sys::SHUT_RDWR,
+        } as core::ffi::c_int;
+        unsafe {
+            cvt(esp_idf_sys::lwip_shutdown(self.inner.0, how))?;
+        }
+        Ok(())
+    }
+
+    pub fn set_nodelay(&self, nodelay: bool) -> Result<(), IOError> {
+        self.inner.setsockopt(
+            esp_idf_sys::IPPROTO_TCP as core::ffi::c_int,
+            esp_idf_sys::TCP_NODELAY as core::ffi::c_int,
+            nodelay as core::ffi::c_int,
+        )
+    }
+
+    pub fn nodelay(&self) -> Result<bool, IOError> {
+        let value: core::ffi::c_int = self.inner.getsockopt(
+            esp_idf_sys::IPPROTO_TCP as core::ffi::c_int,
+            esp_idf_sys::TCP_NODELAY as core::ffi::c_int,
+        )?;
+        Ok(value != 0)
+    }
+
+    pub fn set_keepalive(&self, keepalive: bool) -> Result<(), IOError> {
+        self.inner.setsockopt(
+            esp_idf_sys::SOL_SOCKET as core::ffi::c_int,
+            esp_idf_sys::SO_KEEPALIVE as core::ffi::c_int,
+            keepalive as core::ffi::c_int,
+        )
+    }
+
+    pub fn keepalive(&self) -> Result<bool, IOError> {
+        let value: core::ffi::c_int = self.inner.getsockopt(
+            esp_idf_sys::SOL_SOCKET as core::ffi::c_int,
+            esp_idf_sys::SO_KEEPALIVE as core::ffi::c_int,
+        )?;
+        Ok(value != 0)
+    }
+
+    /// Set `SO_RCVTIMEO`.
+    ///
+    /// Every socket this crate creates is put in non-blocking mode (see
+    /// `Socket::set_nonblocking`, applied in `connect`/`bind`/`accept`), so `read`/
+    /// `read_vectored` return `EWOULDBLOCK` immediately rather than ever blocking
+    /// long enough for this timeout to fire — it has no effect on those sockets.
+    /// It only matters for a blocking fd: one wrapped with `from_raw_fd` (which
+    /// leaves the fd's mode as it is), or one taken out of non-blocking mode after
+    /// `into_raw_fd`.
+    pub fn set_read_timeout(&self, timeout: Option<core::time::Duration>) -> Result<(), IOError> {
+        self.inner.setsockopt(
+            esp_idf_sys::SOL_SOCKET as core::ffi::c_int,
+            esp_idf_sys::SO_RCVTIMEO as core::ffi::c_int,
+            duration_to_timeval(timeout)?,
+        )
+    }
+
+    /// Set `SO_SNDTIMEO`. See the caveat on [`TcpStream::set_read_timeout`]: this
+    /// has no effect on `write`/`write_vectored`, which already run on a
+    /// non-blocking socket.
+    pub fn set_write_timeout(&self, timeout: Option<core::time::Duration>) -> Result<(), IOError> {
+        self.inner.setsockopt(
+            esp_idf_sys::SOL_SOCKET as core::ffi::c_int,
+            esp_idf_sys::SO_SNDTIMEO as core::ffi::c_int,
+            duration_to_timeval(timeout)?,
+        )
+    }
+
+    pub fn read_timeout(&self) -> Result<Option<core::time::Duration>, IOError> {
+        let timeval: esp_idf_sys::timeval = self.inner.getsockopt(
+            esp_idf_sys::SOL_SOCKET as core::ffi::c_int,
+            esp_idf_sys::SO_RCVTIMEO as core::ffi::c_int,
+        )?;
+        Ok(timeval_to_duration(timeval))
+    }
+
+    pub fn write_timeout(&self) -> Result<Option<core::time::Duration>, IOError> {
+        let timeval: esp_idf_sys::timeval = self.inner.getsockopt(
+            esp_idf_sys::SOL_SOCKET as core::ffi::c_int,
+            esp_idf_sys::SO_SNDTIMEO as core::ffi::c_int,
+        )?;
+        Ok(timeval_to_duration(timeval))
+    }
+}
+
+/// Convert to the `timeval` `SO_RCVTIMEO`/`SO_SNDTIMEO` expect. `{0, 0}` means "no
+/// timeout" to lwIP, so a zero-duration `Some` would silently turn into "block
+/// forever" instead of the requested "return immediately" — reject it with
+/// `EINVAL`, mirroring std's `TcpStream::set_read_timeout`.
+fn duration_to_timeval(timeout: Option<core::time::Duration>) -> Result<esp_idf_sys::timeval, IOError> {
+    match timeout {
+        Some(timeout) if timeout == core::time::Duration::ZERO => {
+            Err(esp_idf_sys::EINVAL as IOError)
+        }
+        Some(timeout) => Ok(esp_idf_sys::timeval {
+            tv_sec: timeout.as_secs() as _,
+            tv_usec: timeout.subsec_micros() as _,
+        }),
+        None => Ok(esp_idf_sys::timeval {
+            tv_sec: 0,
+            tv_usec: 0,
+        }),
+    }
+}
+
+fn timeval_to_duration(timeval: esp_idf_sys::timeval) -> Option<core::time::Duration> {
+    if timeval.tv_sec == 0 && timeval.tv_usec == 0 {
+        None
+    } else {
+        Some(core::time::Duration::new(
+            timeval.tv_sec as u64,
+            timeval.tv_usec as u32 * 1000,
+        ))
+    }
+}
+
+#[derive(Debug)]
+pub struct UdpSocket {
+    inner: Socket,
+}
+
+impl UdpSocket {
+    pub fn bind(addr: &SocketAddr) -> Result<UdpSocket, IOError> {
+        let sock = Socket::new(addr, esp_idf_sys::SOCK_DGRAM as core::ffi::c_int)?;
+        sock.set_nonblocking()?;
+        unsafe {
+            let (sockaddr, addr_len) = addr.into_inner();
+            cvt(esp_idf_sys::lwip_bind(sock.0, sockaddr.as_ptr(), addr_len))?;
+        }
+        Ok(UdpSocket { inner: sock })
+    }
+
+    pub fn connect(&self, addr: &SocketAddr) -> Result<(), IOError> {
+        unsafe {
+            let (sockaddr, addr_len) = addr.into_inner();
+            cvt(esp_idf_sys::lwip_connect(
+                self.inner.0,
+                sockaddr.as_ptr(),
+                addr_len,
+            ))?;
+        }
+        Ok(())
+    }
+
+    pub async fn send_to(&self, buf: &[u8], addr: &SocketAddr) -> Result<i32, IOError> {
+        future::poll_fn(|cx| self.inner.poll_send_to(cx, buf, addr)).await
+    }
+
+    pub async fn recv_from(&self, buf: &mut [u8]) -> Result<(i32, SocketAddr), IOError> {
+        future::poll_fn(|cx| self.inner.poll_recv_from(cx, buf)).await
+    }
+
+    pub async fn send(&self, buf: &[u8]) -> Result<i32, IOError> {
+        future::poll_fn(|cx| self.inner.poll_write(cx, buf)).await
+    }
+
+    pub async fn recv(&self, buf: &mut [u8]) -> Result<i32, IOError> {
+        future::poll_fn(|cx| self.inner.poll_read(cx, buf)).await
+    }
+}
+
+/// A buffer to receive into, used with [`TcpStream::read_vectored`]. Has the same
+/// memory layout as an `iovec`.
+#[repr(transparent)]
+pub struct IoSliceMut<'a> {
+    vec: esp_idf_sys::iovec,
+    _p: core::marker::PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> IoSliceMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> IoSliceMut<'a> {
+        IoSliceMut {
+            vec: esp_idf_sys::iovec {
+                iov_base: buf.as_mut_ptr() as *mut c_void,
+                iov_len: buf.len(),
+            },
+            _p: core::marker::PhantomData,
+        }
+    }
+}
+
+/// A buffer to send from, used with [`TcpStream::write_vectored`]. Has the same
+/// memory layout as an `iovec`.
+#[repr(transparent)]
+pub struct IoSlice<'a> {
+    vec: esp_idf_sys::iovec,
+    _p: core::marker::PhantomData<&'a [u8]>,
+}
+
+impl<'a> IoSlice<'a> {
+    pub fn new(buf: &'a [u8]) -> IoSlice<'a> {
+        IoSlice {
+            vec: esp_idf_sys::iovec {
+                iov_base: buf.as_ptr() as *mut c_void,
+                iov_len: buf.len(),
+            },
+            _p: core::marker::PhantomData,
+        }
     }
 }
 
@@ -183,3 +682,98 @@ pub(crate) fn sockaddr_to_addr(
         _ => Err(-1),
     }
 }
+
+/// Like [`sockaddr_to_addr`], but reads the family/address straight through a raw
+/// `sockaddr` pointer instead of a `&sockaddr_storage` reference. Used for
+/// `addrinfo::ai_addr`, which is only sized to hold the concrete address family
+/// lwIP allocated it for, so a full `&sockaddr_storage` would read past the end
+/// of that allocation.
+fn sockaddr_to_addr_raw(addr: *const esp_idf_sys::sockaddr, len: usize) -> Result<SocketAddr, IOError> {
+    match unsafe { (*addr).sa_family as u32 } {
+        esp_idf_sys::AF_INET => {
+            assert!(len >= core::mem::size_of::<esp_idf_sys::sockaddr_in>());
+            let sin = unsafe {
+                core::ptr::read_unaligned(addr as *const esp_idf_sys::sockaddr_in)
+            };
+            Ok(SocketAddr::V4(SocketAddrV4::from_inner(sin)))
+        }
+        esp_idf_sys::AF_INET6 => {
+            assert!(len >= core::mem::size_of::<esp_idf_sys::sockaddr_in6>());
+            let sin6 = unsafe {
+                core::ptr::read_unaligned(addr as *const esp_idf_sys::sockaddr_in6)
+            };
+            Ok(SocketAddr::V6(SocketAddrV6::from_inner(sin6)))
+        }
+        _ => Err(-1),
+    }
+}
+
+fn cvt_gai(n: core::ffi::c_int) -> Result<(), IOError> {
+    if n == 0 {
+        Ok(())
+    } else {
+        Err(n)
+    }
+}
+
+/// An iterator over the [`SocketAddr`]s a hostname resolves to, returned by
+/// [`lookup_host`]. Frees the underlying `addrinfo` chain on drop.
+pub struct LookupHost {
+    port: u16,
+    next: *mut esp_idf_sys::addrinfo,
+    original: *mut esp_idf_sys::addrinfo,
+}
+
+impl Iterator for LookupHost {
+    type Item = SocketAddr;
+
+    fn next(&mut self) -> Option<SocketAddr> {
+        loop {
+            let cur = unsafe { self.next.as_ref() }?;
+            self.next = cur.ai_next;
+            if let Ok(mut addr) = sockaddr_to_addr_raw(cur.ai_addr, cur.ai_addrlen as usize) {
+                addr.set_port(self.port);
+                return Some(addr);
+            }
+        }
+    }
+}
+
+impl Drop for LookupHost {
+    fn drop(&mut self) {
+        unsafe {
+            esp_idf_sys::lwip_freeaddrinfo(self.original);
+        }
+    }
+}
+
+/// Resolve `host` to the [`SocketAddr`]s it maps to, via `lwip_getaddrinfo`.
+pub fn lookup_host(host: &str, port: u16) -> Result<LookupHost, IOError> {
+    let mut c_host = [0u8; 128];
+    if host.len() >= c_host.len() {
+        return Err(-1);
+    }
+    c_host[..host.len()].copy_from_slice(host.as_bytes());
+
+    let hints = esp_idf_sys::addrinfo {
+        ai_family: esp_idf_sys::AF_UNSPEC as core::ffi::c_int,
+        ai_socktype: esp_idf_sys::SOCK_STREAM as core::ffi::c_int,
+        ..unsafe { core::mem::zeroed() }
+    };
+
+    let mut res: *mut esp_idf_sys::addrinfo = core::ptr::null_mut();
+    unsafe {
+        cvt_gai(esp_idf_sys::lwip_getaddrinfo(
+            c_host.as_ptr() as *const core::ffi::c_char,
+            core::ptr::null(),
+            &hints,
+            &mut res,
+        ))?;
+    }
+
+    Ok(LookupHost {
+        port,
+        next: res,
+        original: res,
+    })
+}
@@ -0,0 +1,137 @@
+use alloc::collections::BTreeMap;
+use core::cell::RefCell;
+use core::ffi::c_int;
+use core::task::Waker;
+use critical_section::Mutex;
+
+#[derive(Default)]
+struct Interest {
+    read: Option<Waker>,
+    write: Option<Waker>,
+}
+
+static REACTOR: Mutex<RefCell<BTreeMap<c_int, Interest>>> = Mutex::new(RefCell::new(BTreeMap::new()));
+
+/// Record that `waker` wants to be woken the next time `fd` becomes readable.
+///
+/// Only one reader waker is kept per fd: if the stored waker would already wake
+/// the same task as `waker`, it is left in place; otherwise `waker` replaces it,
+/// and the previous waker is dropped without being woken. Callers that need more
+/// than one task waiting on the same fd/direction must coordinate that themselves
+/// (e.g. with a shared wrapper future).
+pub(crate) fn register_reader(fd: c_int, waker: &Waker) {
+    critical_section::with(|cs| {
+        let mut table = REACTOR.borrow(cs).borrow_mut();
+        let interest = table.entry(fd).or_default();
+        if !matches!(&interest.read, Some(existing) if existing.will_wake(waker)) {
+            interest.read = Some(waker.clone());
+        }
+    });
+}
+
+/// Record that `waker` wants to be woken the next time `fd` becomes writable.
+///
+/// See [`register_reader`] for the one-writer-per-fd caveat.
+pub(crate) fn register_writer(fd: c_int, waker: &Waker) {
+    critical_section::with(|cs| {
+        let mut table = REACTOR.borrow(cs).borrow_mut();
+        let interest = table.entry(fd).or_default();
+        if !matches!(&interest.write, Some(existing) if existing.will_wake(waker)) {
+            interest.write = Some(waker.clone());
+        }
+    });
+}
+
+/// Drop any pending wakers for `fd`, called when the socket is closed.
+pub(crate) fn deregister(fd: c_int) {
+    critical_section::with(|cs| {
+        REACTOR.borrow(cs).borrow_mut().remove(&fd);
+    });
+}
+
+/// How long a single `lwip_select` call is allowed to block before `poll` re-scans
+/// the interest table. Without this, an fd registered after `select` has already
+/// started waiting on a disjoint set of fds would never be observed until one of
+/// those older fds happened to fire.
+const POLL_TIMEOUT_US: u32 = 100_000;
+
+/// Drive readiness for every registered fd by running them through `lwip_select`,
+/// waking and clearing the waker of each fd that is ready. Intended to be called
+/// in a loop from a background task (e.g. spawned once alongside the executor).
+pub fn poll() {
+    let fds: alloc::vec::Vec<c_int> =
+        critical_section::with(|cs| table_keys(&REACTOR.borrow(cs).borrow()));
+    if fds.is_empty() {
+        return;
+    }
+
+    let mut read_fds: esp_idf_sys::fd_set = unsafe { core::mem::zeroed() };
+    let mut write_fds: esp_idf_sys::fd_set = unsafe { core::mem::zeroed() };
+    let mut max_fd = 0;
+    critical_section::with(|cs| {
+        let table = REACTOR.borrow(cs).borrow();
+        for (&fd, interest) in table.iter() {
+            if interest.read.is_some() {
+                unsafe { esp_idf_sys::lwip_fd_set(fd, &mut read_fds) };
+            }
+            if interest.write.is_some() {
+                unsafe { esp_idf_sys::lwip_fd_set(fd, &mut write_fds) };
+            }
+            if fd > max_fd {
+                max_fd = fd;
+            }
+        }
+    });
+
+    let mut timeout = esp_idf_sys::timeval {
+        tv_sec: 0,
+        tv_usec: POLL_TIMEOUT_US as _,
+    };
+    let ready = unsafe {
+        esp_idf_sys::lwip_select(
+            max_fd + 1,
+            &mut read_fds,
+            &mut write_fds,
+            core::ptr::null_mut(),
+            &mut timeout,
+        )
+    };
+    if ready <= 0 {
+        return;
+    }
+
+    // Only take the ready wakers out of the table while interrupts are disabled;
+    // run the actual `wake()` calls afterwards. A woken task is free to re-poll
+    // synchronously and call back into `register_reader`/`register_writer`/
+    // `deregister`, which would re-enter this same `RefCell` (panicking) and run
+    // arbitrary task code with interrupts disabled if done from inside the
+    // critical section.
+    let mut ready_wakers: alloc::vec::Vec<Waker> = alloc::vec::Vec::new();
+    critical_section::with(|cs| {
+        let mut table = REACTOR.borrow(cs).borrow_mut();
+        for &fd in fds.iter() {
+            let readable = unsafe { esp_idf_sys::lwip_fd_isset(fd, &mut read_fds) } != 0;
+            let writable = unsafe { esp_idf_sys::lwip_fd_isset(fd, &mut write_fds) } != 0;
+            if let Some(interest) = table.get_mut(&fd) {
+                if readable {
+                    if let Some(waker) = interest.read.take() {
+                        ready_wakers.push(waker);
+                    }
+                }
+                if writable {
+                    if let Some(waker) = interest.write.take() {
+                        ready_wakers.push(waker);
+                    }
+                }
+            }
+        }
+    });
+
+    for waker in ready_wakers {
+        waker.wake();
+    }
+}
+
+fn table_keys(table: &BTreeMap<c_int, Interest>) -> alloc::vec::Vec<c_int> {
+    table.keys().copied().collect()
+}